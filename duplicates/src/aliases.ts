@@ -0,0 +1,135 @@
+import Stream from "stream";
+import { createWriteStream } from "fs";
+
+import { MongoClient } from "mongodb";
+import { userSchema } from "./schema";
+import Spinnies from "spinnies";
+import { join } from "path";
+
+const spinnies = new Spinnies({
+  spinner: {
+    interval: 80,
+    frames: [
+      "▰▱▱▱▱▱▱",
+      "▰▰▱▱▱▱▱",
+      "▰▰▰▱▱▱▱",
+      "▰▰▰▰▱▱▱",
+      "▰▰▰▰▰▱▱",
+      "▰▰▰▰▰▰▱",
+      "▰▰▰▰▰▰▰",
+      "▰▱▱▱▱▱▱",
+    ],
+  },
+});
+
+// Gmail ignores dots in the local part, and googlemail.com is the same mailbox.
+const gmailDomains = ["gmail.com", "googlemail.com"];
+
+(async () => {
+  const dbClient = await MongoClient.connect(process.env.MONGO_URI as string, {
+    replicaSet: "atlas-axsdig-shard-0",
+    maxPoolSize: 20,
+  });
+  console.log("Connected to MongoDB");
+
+  const filePath = join(process.cwd(), "results", "probable-dupe-emails.csv");
+  const file = createWriteStream(filePath);
+
+  const db = dbClient.db("freecodecamp");
+  const users = db.collection("user");
+
+  const rs = new Stream.Readable({ objectMode: true });
+  rs._read = () => {};
+
+  rs.on("data", (document: userSchema) => {
+    file.write(
+      `${document._id},${document.emails.join(";")},${document.ids.join(
+        ","
+      )}\n`
+    );
+  });
+
+  const stream = users
+    .aggregate(
+      [
+        {
+          $match: {
+            email: { $type: "string" },
+          },
+        },
+        {
+          $project: {
+            email: { $toLower: "$email" },
+          },
+        },
+        {
+          $addFields: {
+            local: { $arrayElemAt: [{ $split: ["$email", "@"] }, 0] },
+            domain: { $arrayElemAt: [{ $split: ["$email", "@"] }, -1] },
+          },
+        },
+        {
+          // strip the +tag alias from the local part
+          $addFields: {
+            local: { $arrayElemAt: [{ $split: ["$local", "+"] }, 0] },
+            isGmail: { $in: ["$domain", gmailDomains] },
+          },
+        },
+        {
+          $group: {
+            _id: {
+              $concat: [
+                {
+                  $cond: [
+                    "$isGmail",
+                    {
+                      $replaceAll: {
+                        input: "$local",
+                        find: ".",
+                        replacement: "",
+                      },
+                    },
+                    "$local",
+                  ],
+                },
+                "@",
+                { $cond: ["$isGmail", "gmail.com", "$domain"] },
+              ],
+            },
+            count: { $sum: 1 },
+            ids: { $push: "$_id" },
+            emails: { $addToSet: "$email" },
+          },
+        },
+        {
+          // clusters with a single distinct email are exact matches, which
+          // are already covered by the duplicate emails report.
+          $match: {
+            count: { $gt: 1 },
+            "emails.1": { $exists: true },
+          },
+        },
+      ],
+      {
+        allowDiskUse: true,
+        hint: "_id_1_email_1",
+      }
+    )
+    .batchSize(50)
+    .stream();
+
+  spinnies.add("query", {
+    color: "magenta",
+    text: "Running database query...",
+  });
+
+  stream.on("data", (document: userSchema) => {
+    rs.push(document);
+  });
+
+  stream.on("end", () => {
+    rs.push(null);
+    dbClient.close();
+    spinnies.succeed("query", { text: "All done!" });
+  });
+})();